@@ -9,12 +9,31 @@
 //! 2. We fire our request at time = 0s.
 //! 3. We timeout after time = 10s
 //! 4. We fire our next request at time t = 13s
+//!
+//! This is the `MissedTickBehavior::Delay` policy and the default. Use
+//! `Interval::set_missed_tick_behavior` to pick `Burst` or `Skip` instead.
+//!
+//! When the pool or wallet is down, `MiningInfo` requests time out and then
+//! fire in bursts. Call `Interval::set_backoff` to opt into exponential
+//! backoff with jitter instead, reporting each poll's outcome via
+//! `record_success` / `record_failure`. This keeps a dead pool from being
+//! hammered and, when many miners share one pool, the jitter desynchronizes
+//! reconnect storms.
+//!
+//! The main way to drive an `Interval` is `interval.tick().await`. The
+//! `Stream` impl is an optional, feature-gated convenience for call sites
+//! that still need to compose with other streams.
 
-use futures::{try_ready, Future, Poll, Stream};
-use std::time::{Duration, Instant};
-use tokio::clock;
-use tokio::timer::Delay;
-use tokio::timer::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::time::{self, Delay};
+
+// Reuse tokio's own re-export instead of pulling in `futures_core` as a new
+// direct dependency; tokio is already required for `Delay`.
+#[cfg(feature = "stream")]
+use tokio::stream::Stream;
 
 /// State of the interval stream.
 #[derive(Debug)]
@@ -25,7 +44,80 @@ enum State {
     Awaiting,
 }
 
-/// A stream representing notifications at fixed interval
+/// Defines how an `Interval` behaves when a tick fires later than scheduled,
+/// e.g. because processing the previous item (a `MiningInfo` request) took
+/// longer than `duration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Schedule the next deadline off the ideal timeline, i.e. the previous
+    /// scheduled deadline plus `duration`, and keep firing back-to-back
+    /// until caught up. Useful when every missed polling slot must be
+    /// detected.
+    Burst,
+
+    /// Schedule the next deadline `duration` after the instant the item
+    /// actually completed. This is the default and avoids request pile-up
+    /// when the pool or wallet is slow.
+    Delay,
+
+    /// Discard missed slots and re-align to the original phase, so polls
+    /// stay on a fixed wall-clock grid regardless of how long a request
+    /// took.
+    Skip,
+}
+
+impl Default for MissedTickBehavior {
+    fn default() -> Self {
+        MissedTickBehavior::Delay
+    }
+}
+
+/// Configuration for the exponential backoff applied when the consumer
+/// reports failed polls through `Interval::record_failure`, e.g. because
+/// the pool or wallet timed out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackoffConfig {
+    /// The starting delay. Also the delay restored by `record_success`.
+    pub base: Duration,
+
+    /// The scaled delay is clamped to this before jitter is applied, so a
+    /// jittered delay can exceed it by up to `jitter_fraction`.
+    pub max: Duration,
+
+    /// Factor the current delay is multiplied by on each recorded failure.
+    pub multiplier: f64,
+
+    /// Fraction of the delay to randomly jitter by, e.g. `0.1` for ±10%.
+    pub jitter_fraction: f64,
+}
+
+/// A tiny xorshift64 PRNG, seeded once from the current time. Used to
+/// jitter backoff delays so that many miners sharing one pool don't all
+/// reconnect in lockstep.
+#[derive(Debug)]
+struct Jitter(u64);
+
+impl Jitter {
+    fn new() -> Jitter {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15);
+        Jitter(seed | 1)
+    }
+
+    /// Returns a pseudo-random value in `[-bound, bound)`.
+    fn next_in(&mut self, bound: f64) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        let unit = (self.0 >> 11) as f64 / (1u64 << 53) as f64;
+        (unit * 2.0 - 1.0) * bound
+    }
+}
+
+/// Yields the scheduled deadline of each tick, spaced according to the
+/// configured `MissedTickBehavior` and backoff.
 #[derive(Debug)]
 pub struct Interval {
     /// Future that completes the next time the `Interval` yields a value.
@@ -34,6 +126,24 @@ pub struct Interval {
     /// The duration between values yielded by `Interval`.
     duration: Duration,
 
+    /// The instant the first tick was scheduled at. Used by
+    /// `MissedTickBehavior::Skip` to re-align to the original phase.
+    start: Instant,
+
+    /// How to behave when a tick fires later than scheduled.
+    missed_tick_behavior: MissedTickBehavior,
+
+    /// The delay currently in effect, used instead of `duration` once
+    /// backoff is enabled.
+    effective_delay: Duration,
+
+    /// Opt-in backoff applied on `record_failure`. `None` until
+    /// `set_backoff` is called.
+    backoff: Option<BackoffConfig>,
+
+    /// Source of jitter for the backoff delay.
+    jitter: Jitter,
+
     state: State,
 }
 
@@ -54,7 +164,7 @@ impl Interval {
             "`duration` must be non-zero."
         );
 
-        Interval::new_with_delay(Delay::new(at), duration)
+        Interval::new_with_delay(time::delay_until(at.into()), duration)
     }
 
     /// Creates new `Interval` that yields with interval of `duration`.
@@ -67,43 +177,274 @@ impl Interval {
     ///
     /// This function panics if `duration` is zero.
     pub fn new_interval(duration: Duration) -> Interval {
-        Interval::new(clock::now() + duration, duration)
+        Interval::new(Instant::now() + duration, duration)
     }
 
     pub(crate) fn new_with_delay(delay: Delay, duration: Duration) -> Interval {
+        let start = delay.deadline().into();
         Interval {
             delay,
             duration,
-            state: State::Delaying,
+            start,
+            missed_tick_behavior: MissedTickBehavior::default(),
+            effective_delay: duration,
+            backoff: None,
+            jitter: Jitter::new(),
+            state: State::Awaiting,
+        }
+    }
+
+    /// Sets the behavior to use when a tick fires later than scheduled.
+    ///
+    /// Defaults to `MissedTickBehavior::Delay`, which preserves the
+    /// `Interval`'s original behavior.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Enables exponential backoff with jitter, starting at `config.base`.
+    ///
+    /// Once enabled, call `record_success` or `record_failure` after each
+    /// poll to adjust the effective delay used by the interval.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `config.base` is greater than `config.max`,
+    /// if `config.multiplier` is not greater than `1.0`, or if
+    /// `config.jitter_fraction` is outside of `[0.0, 1.0]`.
+    pub fn set_backoff(&mut self, config: BackoffConfig) {
+        assert!(
+            config.base <= config.max,
+            "`BackoffConfig::base` must be <= `max`."
+        );
+        assert!(
+            config.multiplier > 1.0,
+            "`BackoffConfig::multiplier` must be greater than 1.0."
+        );
+        assert!(
+            (0.0..=1.0).contains(&config.jitter_fraction),
+            "`BackoffConfig::jitter_fraction` must be in `[0.0, 1.0]`."
+        );
+
+        self.effective_delay = config.base;
+        self.backoff = Some(config);
+    }
+
+    /// Reports that the last poll succeeded, resetting the effective delay
+    /// back to the configured base.
+    pub fn record_success(&mut self) {
+        if let Some(config) = self.backoff {
+            self.effective_delay = config.base;
         }
     }
+
+    /// Reports that the last poll failed, e.g. because the pool or wallet
+    /// timed out. Multiplies the effective delay by `config.multiplier`,
+    /// clamps it at `config.max`, and adds random jitter. The next call to
+    /// `poll_tick` schedules off the new `effective_delay`, so the pending
+    /// delay isn't touched here.
+    pub fn record_failure(&mut self) {
+        let config = match self.backoff {
+            Some(config) => config,
+            None => return,
+        };
+
+        let scaled = self.effective_delay.mul_f64(config.multiplier);
+        let clamped = if scaled > config.max { config.max } else { scaled };
+        let jitter = self.jitter.next_in(config.jitter_fraction);
+        let jittered = clamped.mul_f64((1.0 + jitter).max(0.0));
+
+        self.effective_delay = jittered;
+    }
+
+    /// Polls for the next tick, advancing `delay` according to the
+    /// configured `MissedTickBehavior`. Shared by `tick` and the optional
+    /// `Stream` impl.
+    ///
+    /// `Awaiting` just waits out the deadline currently armed on `delay`
+    /// (the original `at` on the very first call), so that deadline is
+    /// honored exactly instead of being discarded. Only once it fires do we
+    /// move to `Delaying` and compute where the *next* deadline goes.
+    fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        if let State::Awaiting = self.state {
+            let now: Instant = self.delay.deadline().into();
+            return match Pin::new(&mut self.delay).poll(cx) {
+                Poll::Ready(()) => {
+                    self.state = State::Delaying;
+                    Poll::Ready(now)
+                }
+                Poll::Pending => Poll::Pending,
+            };
+        }
+
+        // `State::Delaying`: the previous deadline just fired (or this is a
+        // `Burst` catch-up continuing from one that did); schedule the next
+        // one.
+        let now: Instant = self.delay.deadline().into();
+        let next = match self.missed_tick_behavior {
+            // Schedule off the ideal timeline so missed slots are caught up
+            // on as fast as possible.
+            MissedTickBehavior::Burst => now + self.effective_delay,
+            // The next interval value is `duration` (or the current
+            // backed-off delay) after the instant the previous item was
+            // consumed, i.e. now.
+            MissedTickBehavior::Delay => time::Instant::now().into_std() + self.effective_delay,
+            // Re-align to the original phase, skipping over any slots that
+            // were missed.
+            MissedTickBehavior::Skip => {
+                let now = time::Instant::now().into_std();
+                let elapsed = now.duration_since(self.start).as_nanos();
+                let duration = self.effective_delay.as_nanos();
+                let remainder = Duration::from_nanos((elapsed % duration) as u64);
+                now + (self.effective_delay - remainder)
+            }
+        };
+        self.delay.reset(next.into());
+
+        // A `Burst` catch-up can compute a `next` deadline that's already due.
+        // Yield it right away instead of waiting for another wakeup, and
+        // stay in `Delaying` so the following call recomputes from this
+        // deadline instead of re-emitting it.
+        match Pin::new(&mut self.delay).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(next),
+            Poll::Pending => {
+                self.state = State::Awaiting;
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Completes the next time the `Interval` yields a value, returning the
+    /// deadline `Instant`.
+    ///
+    /// Delays only once the prior item has been consumed, so an overrunning
+    /// `MiningInfo` request doesn't cause requests to pile up; see the
+    /// module docs for the exact catch-up policy in effect.
+    pub async fn tick(&mut self) -> Instant {
+        std::future::poll_fn(|cx| self.poll_tick(cx)).await
+    }
 }
 
+/// Optional `Stream` impl for call sites that still need to compose an
+/// `Interval` with other streams. Prefer `Interval::tick` directly.
+#[cfg(feature = "stream")]
 impl Stream for Interval {
     type Item = Instant;
-    type Error = Error;
 
-    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        // Get the `now` by looking at the `delay` deadline
-        let now = self.delay.deadline();
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_tick(cx).map(Some)
+    }
+}
 
-        match self.state {
-            State::Delaying => {
-                // The next interval value is `duration` after the one that just
-                // yielded.
-                self.delay.reset(Instant::now() + self.duration);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                self.state = State::Awaiting;
-            }
-            State::Awaiting => {
-                self.state = State::Delaying;
-            }
+    #[tokio::test]
+    async fn burst_catches_up_on_every_missed_slot_exactly_once() {
+        tokio::time::pause();
+        let start = Instant::now();
+        let mut interval = Interval::new(start, Duration::from_secs(1));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+        // The first tick fires immediately at `start`, per the documented
+        // contract.
+        assert_eq!(interval.tick().await, start);
+
+        // Simulate an overrunning `MiningInfo` request: 3.7s pass before we
+        // poll for the next tick again.
+        tokio::time::advance(Duration::from_millis(3_700)).await;
+
+        let mut ticks = Vec::new();
+        for _ in 0..3 {
+            ticks.push(interval.tick().await);
         }
 
-        // Wait for the delay to be done
-        let _ = try_ready!(self.delay.poll());
+        // Every missed slot is reported exactly once, back-to-back.
+        assert_eq!(
+            ticks,
+            vec![
+                start + Duration::from_secs(1),
+                start + Duration::from_secs(2),
+                start + Duration::from_secs(3),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn delay_schedules_off_the_completion_time() {
+        tokio::time::pause();
+        let start = Instant::now();
+        let mut interval = Interval::new(start, Duration::from_secs(1));
 
-        // Return the current instant
-        Ok(Some(now).into())
+        assert_eq!(interval.tick().await, start);
+
+        tokio::time::advance(Duration::from_millis(3_700)).await;
+        let completed_at = tokio::time::Instant::now().into_std();
+
+        assert_eq!(interval.tick().await, completed_at + Duration::from_secs(1));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn skip_realigns_to_the_original_phase() {
+        tokio::time::pause();
+        let start = Instant::now();
+        let mut interval = Interval::new(start, Duration::from_secs(1));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        assert_eq!(interval.tick().await, start);
+
+        tokio::time::advance(Duration::from_millis(3_700)).await;
+
+        // Re-aligns to the fixed grid started at `start`, skipping the
+        // slots that fell within the 3.7s overrun.
+        assert_eq!(interval.tick().await, start + Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    async fn skip_uses_the_effective_delay_after_backoff() {
+        tokio::time::pause();
+        let start = Instant::now();
+        let mut interval = Interval::new(start, Duration::from_secs(1));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        interval.set_backoff(BackoffConfig {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(8),
+            multiplier: 2.0,
+            jitter_fraction: 0.0,
+        });
+
+        assert_eq!(interval.tick().await, start);
+
+        interval.record_failure();
+        assert_eq!(interval.effective_delay, Duration::from_secs(2));
+
+        // The grid just widened to 2s, so the next tick lands 2s after
+        // `start`, not 1s.
+        assert_eq!(interval.tick().await, start + Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn backoff_grows_and_clamps_then_resets_on_success() {
+        let config = BackoffConfig {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(8),
+            multiplier: 2.0,
+            jitter_fraction: 0.0,
+        };
+        let mut interval = Interval::new_interval(Duration::from_secs(1));
+        interval.set_backoff(config);
+
+        interval.record_failure();
+        assert_eq!(interval.effective_delay, Duration::from_secs(2));
+        interval.record_failure();
+        assert_eq!(interval.effective_delay, Duration::from_secs(4));
+        interval.record_failure();
+        assert_eq!(interval.effective_delay, Duration::from_secs(8));
+        interval.record_failure();
+        assert_eq!(interval.effective_delay, Duration::from_secs(8));
+
+        interval.record_success();
+        assert_eq!(interval.effective_delay, config.base);
+    }
+}